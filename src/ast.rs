@@ -7,13 +7,15 @@ pub trait TestContext {
 pub enum TestCase<C: TestContext> {
     Background(Scenario<C>),
     Scenario(Scenario<C>),
+    PropSpec(propspec::PropScenario<C>),
 }
 
 impl<C: TestContext> TestCase<C> {
     pub fn name(&self) -> Option<String> {
         match self {
             TestCase::Background(s) => s.name.clone(),
-            TestCase::Scenario(s) => s.name.clone()
+            TestCase::Scenario(s) => s.name.clone(),
+            TestCase::PropSpec(p) => Some(p.name.clone()),
         }
     }
 
@@ -21,30 +23,162 @@ impl<C: TestContext> TestCase<C> {
     pub fn eval(&self, context: C) -> TestResult<C> {
         match self {
             TestCase::Background(s) => s.eval(context),
-            TestCase::Scenario(s) => s.eval(context)
+            TestCase::Scenario(s) => s.eval(context),
+            TestCase::PropSpec(p) => p.eval(),
         }
     }
+
+    /// The scenario-level tags attached to this case. Property specs carry no
+    /// tags of their own and so match on feature-level tags alone.
+    pub fn tags(&self) -> Vec<String> {
+        match self {
+            TestCase::Background(s) => s.tags.clone(),
+            TestCase::Scenario(s) => s.tags.clone(),
+            TestCase::PropSpec(_) => vec![],
+        }
+    }
+}
+
+/// A boolean expression over `@tags`, e.g. `@smoke and not @wip`. Supports
+/// `and`, `or`, `not`, and parentheses, with the usual precedence
+/// (`not` binds tightest, then `and`, then `or`).
+enum TagExpr {
+    Tag(String),
+    Not(Box<TagExpr>),
+    And(Box<TagExpr>, Box<TagExpr>),
+    Or(Box<TagExpr>, Box<TagExpr>),
+}
+
+impl TagExpr {
+    fn parse(input: &str) -> Result<TagExpr, String> {
+        let tokens = tokenize(input);
+        let mut pos = 0;
+        let expr = parse_or(&tokens, &mut pos)?;
+        if pos != tokens.len() {
+            return Err(format!("unexpected token {:?}", tokens[pos]));
+        }
+        Ok(expr)
+    }
+
+    /// Whether this expression holds given the set of tags active on a scenario.
+    fn matches(&self, active: &[&str]) -> bool {
+        match *self {
+            TagExpr::Tag(ref t) => active.iter().any(|a| a == t),
+            TagExpr::Not(ref e) => !e.matches(active),
+            TagExpr::And(ref a, ref b) => a.matches(active) && b.matches(active),
+            TagExpr::Or(ref a, ref b) => a.matches(active) || b.matches(active),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Vec<String> {
+    let spaced = input.replace('(', " ( ").replace(')', " ) ");
+    spaced.split_whitespace().map(|s| s.to_string()).collect()
+}
+
+fn parse_or(tokens: &[String], pos: &mut usize) -> Result<TagExpr, String> {
+    let mut left = parse_and(tokens, pos)?;
+    while tokens.get(*pos).map(|t| t == "or").unwrap_or(false) {
+        *pos += 1;
+        let right = parse_and(tokens, pos)?;
+        left = TagExpr::Or(Box::new(left), Box::new(right));
+    }
+    Ok(left)
+}
+
+fn parse_and(tokens: &[String], pos: &mut usize) -> Result<TagExpr, String> {
+    let mut left = parse_not(tokens, pos)?;
+    while tokens.get(*pos).map(|t| t == "and").unwrap_or(false) {
+        *pos += 1;
+        let right = parse_not(tokens, pos)?;
+        left = TagExpr::And(Box::new(left), Box::new(right));
+    }
+    Ok(left)
+}
+
+fn parse_not(tokens: &[String], pos: &mut usize) -> Result<TagExpr, String> {
+    if tokens.get(*pos).map(|t| t == "not").unwrap_or(false) {
+        *pos += 1;
+        return Ok(TagExpr::Not(Box::new(parse_not(tokens, pos)?)));
+    }
+    parse_atom(tokens, pos)
+}
+
+fn parse_atom(tokens: &[String], pos: &mut usize) -> Result<TagExpr, String> {
+    match tokens.get(*pos) {
+        Some(t) if t == "(" => {
+            *pos += 1;
+            let inner = parse_or(tokens, pos)?;
+            match tokens.get(*pos) {
+                Some(c) if c == ")" => {
+                    *pos += 1;
+                    Ok(inner)
+                }
+                _ => Err("expected closing ')'".to_string()),
+            }
+        }
+        Some(t) if t.starts_with('@') => {
+            *pos += 1;
+            Ok(TagExpr::Tag(t[1..].to_string()))
+        }
+        Some(t) => Err(format!("expected a tag, found {:?}", t)),
+        None => Err("unexpected end of tag expression".to_string()),
+    }
+}
+
+/// A minimal failing input discovered by shrinking a `PropSpec`: the generated
+/// integer inputs that still reproduce the failure, and the index of the step
+/// that returned `false` for them.
+pub struct Counterexample {
+    pub inputs: Vec<i64>,
+    pub failing_step: usize,
 }
 
 pub struct TestResult<C: TestContext> {
     pub test_case_name: String,
     pub pass: bool,
-    pub context: C
+    pub context: C,
+    /// Present only for a failed `PropSpec`, carrying the shrunk counterexample.
+    pub counterexample: Option<Counterexample>,
 }
 
 /// A feature is a collection of test cases.
 pub struct Feature<C: TestContext> {
     pub name: String,
     pub comment: String,
+    pub tags: Vec<String>,
     pub background: Option<TestCase<C>>,
     pub test_cases: Vec<TestCase<C>>,
 }
 
 impl<C: TestContext> Feature<C> {
     pub fn eval(&self) -> Vec<TestResult<C>> {
+        self.eval_matching(|_| true)
+    }
+
+    /// Evaluate only the scenarios whose tags satisfy `expr`, a boolean tag
+    /// expression such as `@smoke and not @wip`. Feature-level tags are
+    /// inherited by every scenario. Returns an error only if `expr` itself is
+    /// malformed.
+    pub fn eval_filtered(&self, expr: &str) -> Result<Vec<TestResult<C>>, String> {
+        let ast = TagExpr::parse(expr)?;
+        Ok(self.eval_matching(|tc| {
+            let mut active: Vec<&str> = self.tags.iter().map(|s| s.as_str()).collect();
+            active.extend(tc.tags().iter().map(|s| s.as_str()));
+            ast.matches(&active)
+        }))
+    }
 
+    fn eval_matching<F>(&self, keep: F) -> Vec<TestResult<C>>
+    where
+        F: Fn(&TestCase<C>) -> bool,
+    {
         let mut results = vec![];
         for tc in self.test_cases.iter() {
+            if !keep(tc) {
+                continue;
+            }
+
             let mut context = C::new();
 
             if let Some(TestCase::Background(ref bg)) = self.background {
@@ -69,22 +203,24 @@ impl<C: TestContext> Feature<C> {
 
 pub struct Scenario<TC: TestContext> {
     pub name: Option<String>,
-    pub steps: Vec<Box<Step<TC>>>,
+    pub tags: Vec<String>,
+    pub steps: Vec<(Box<Step<TC>>, StepArg)>,
 }
 
 impl<C: TestContext> Scenario<C> {
     /// Execute a scenario by running each step in order, with mutable access to
-    /// the context.
+    /// the context and the argument block attached to the step.
     pub fn eval(&self, mut context: C) -> TestResult<C> {
-        for s in self.steps.iter() {
-            if !s.eval(&mut context) {
+        for &(ref s, ref arg) in self.steps.iter() {
+            if !s.eval(&mut context, arg) {
                 return TestResult {
                     test_case_name: match self.name.as_ref() {
                         Some(s) => s.clone(),
                         None => "".to_string()
                     },
                     pass: false,
-                    context: context
+                    context: context,
+                    counterexample: None,
                 };
             }
         }
@@ -95,14 +231,24 @@ impl<C: TestContext> Scenario<C> {
                 None => "".to_string()
             },
             pass: true,
-            context: context
+            context: context,
+            counterexample: None,
         }
     }
 }
 
+/// An argument block attached to a step: classic Gherkin lets a step carry
+/// either a triple-quoted doc string or an inline pipe table on the following
+/// lines. Steps with no attached block see `StepArg::None`.
+pub enum StepArg {
+    None,
+    DocString(String),
+    Table(Vec<Vec<String>>),
+}
+
 /// A specific step which makes up a scenario. Users should create their own
 /// implementations of this trait, which are returned by their step parsers.
 pub trait Step<C: TestContext> {
-    fn eval(&self, &mut C) -> bool;
+    fn eval(&self, &mut C, &StepArg) -> bool;
 }
 
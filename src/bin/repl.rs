@@ -0,0 +1,62 @@
+//! A small interactive runner: type or paste `Scenario:` blocks on stdin and
+//! watch each step execute against a fresh context. Steps are the same digit
+//! encoding used by the parser's own tests (`G1`, `W2`, `T3`, ...), which keeps
+//! the binary dependency-free while still exercising the full pipeline.
+
+#[macro_use]
+extern crate combine;
+extern crate rherkin;
+
+use std::io;
+
+use combine::Parser;
+use combine::char::digit;
+use combine::token;
+
+use rherkin::ast::{Step, StepArg, TestContext};
+use rherkin::parser::BoxedStep;
+use rherkin::propspec::PropContext;
+use rherkin::runner::run_interactive;
+
+#[derive(Debug)]
+struct ReplContext {
+    executed_steps: Vec<u32>,
+}
+
+impl TestContext for ReplContext {
+    fn new() -> ReplContext {
+        ReplContext {
+            executed_steps: vec![],
+        }
+    }
+}
+
+impl PropContext for ReplContext {
+    fn set_number(&mut self, _name: &str, _value: i64) {}
+}
+
+struct ReplStep {
+    num: u32,
+}
+
+impl Step<ReplContext> for ReplStep {
+    fn eval(&self, context: &mut ReplContext, _arg: &StepArg) -> bool {
+        context.executed_steps.push(self.num);
+        true
+    }
+}
+
+fn main() {
+    let num_digit = || digit().map(|c| c.to_digit(10).unwrap());
+    let given = struct_parser! { ReplStep { _: token('G'), num: num_digit() } };
+    let when = struct_parser! { ReplStep { _: token('W'), num: num_digit() } };
+    let then = struct_parser! { ReplStep { _: token('T'), num: num_digit() } };
+
+    let stdin = io::stdin();
+    run_interactive(
+        stdin.lock(),
+        given.map(|x| BoxedStep { val: Box::new(x) }),
+        when.map(|x| BoxedStep { val: Box::new(x) }),
+        then.map(|x| BoxedStep { val: Box::new(x) }),
+    ).expect("error reading from stdin");
+}
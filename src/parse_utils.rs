@@ -5,7 +5,7 @@ use combine::Stream;
 use combine::ParseError;
 
 use combine::char::newline;
-use combine::{Parser, many, many1, none_of, try, eof};
+use combine::{Parser, many, many1, none_of, token, try, eof};
 
 /// Match a single non-newline character
 ///
@@ -110,3 +110,134 @@ where I: Stream<Item = char>,
     many(try(until_eol()))
         .map(|lines: Vec<String>| lines.join("\n"))
 }
+
+
+/// Parse a single cell of a pipe-delimited table. A cell is the run of
+/// characters up to the next `|` or end of line, trimmed of surrounding
+/// whitespace. A literal pipe may be embedded by escaping it as `\|`.
+///
+/// # Examples
+//
+/// ```
+/// # extern crate combine;
+/// # extern crate rherkin;
+/// # use combine::*;
+/// # use rherkin::parse_utils::cell;
+/// # fn main() {
+/// let mut parser = cell();
+/// let result = parser.parse("  a b  |rest");
+/// assert_eq!(result, Ok(("a b".to_string(), "|rest")));
+/// # }
+/// ```
+pub fn cell<I>() -> impl Parser<Input = I, Output = String>
+where I: Stream<Item = char>,
+      I::Error: ParseError<I::Item, I::Range, I::Position>
+{
+    let escaped = (token('\\'), token('|')).map(|_| '|');
+    let raw = none_of("|\r\n".chars());
+    many(try(escaped).or(raw)).map(|s: String| s.trim().to_string())
+}
+
+
+/// Parse a single table row: a `|`-framed sequence of cells terminated by an end
+/// of line. Each cell is bounded by the pipe on either side of it, so `| a | b |`
+/// yields exactly two cells. Intentional empty cells (`| a | |`) are preserved.
+///
+/// # Examples
+//
+/// ```
+/// # extern crate combine;
+/// # extern crate rherkin;
+/// # use combine::*;
+/// # use rherkin::parse_utils::table_row;
+/// # fn main() {
+/// let mut parser = table_row();
+/// let result = parser.parse("| a | b |\nrest");
+/// assert_eq!(result, Ok((vec!["a".to_string(), "b".to_string()], "rest")));
+/// # }
+/// ```
+pub fn table_row<I>() -> impl Parser<Input = I, Output = Vec<String>>
+where I: Stream<Item = char>,
+      I::Error: ParseError<I::Item, I::Range, I::Position>
+{
+    let framed_cell = (cell(), token('|')).map(|(c, _)| c);
+    (token('|'), many1::<Vec<String>, _>(framed_cell), eol()).map(|(_, cells, _)| cells)
+}
+
+
+/// Parse one or more `table_row`s into a table. The first row is conventionally
+/// the header; callers are responsible for interpreting the remaining rows.
+///
+/// # Examples
+//
+/// ```
+/// # extern crate combine;
+/// # extern crate rherkin;
+/// # use combine::*;
+/// # use rherkin::parse_utils::table;
+/// # fn main() {
+/// let mut parser = table();
+/// let result = parser.parse("| a | b |\n| 1 | 2 |\n");
+/// assert_eq!(result.unwrap().0,
+///            vec![vec!["a".to_string(), "b".to_string()],
+///                 vec!["1".to_string(), "2".to_string()]]);
+/// # }
+/// ```
+pub fn table<I>() -> impl Parser<Input = I, Output = Vec<Vec<String>>>
+where I: Stream<Item = char>,
+      I::Error: ParseError<I::Item, I::Range, I::Position>
+{
+    many1(try(table_row()))
+}
+
+
+/// Strip the common leading indentation shared by every non-blank line of a
+/// doc string, so the captured text matches what the author saw in the source.
+fn strip_common_indent(lines: &[String]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|l| if l.len() >= indent { l[indent..].to_string() } else { l.clone() })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+
+/// Parse a triple-quoted doc string: a line that is exactly `"""`, then the
+/// following lines verbatim up to a closing `"""` line. The common leading
+/// indentation of the captured block is stripped.
+///
+/// # Examples
+//
+/// ```
+/// # extern crate combine;
+/// # extern crate rherkin;
+/// # use combine::*;
+/// # use rherkin::parse_utils::doc_string;
+/// # fn main() {
+/// let mut parser = doc_string();
+/// let result = parser.parse("\"\"\"\n    one\n    two\n\"\"\"\nrest");
+/// assert_eq!(result, Ok(("one\ntwo".to_string(), "rest")));
+/// # }
+/// ```
+pub fn doc_string<I>() -> impl Parser<Input = I, Output = String>
+where I: Stream<Item = char>,
+      I::Error: ParseError<I::Item, I::Range, I::Position>
+{
+    use combine::not_followed_by;
+
+    let fence = || (token('"'), token('"'), token('"'));
+    let text_line = || (many::<String, _>(non_newline()), eol()).map(|(s, _)| s);
+    let content = many::<Vec<String>, _>(
+        try((not_followed_by(fence()), text_line()).map(|(_, line)| line)),
+    );
+
+    (fence(), eol(), content, fence(), eol())
+        .map(|(_, _, lines, _, _): (_, _, Vec<String>, _, _)| strip_common_indent(&lines))
+}
@@ -1,34 +1,193 @@
-use ast::{Feature, Scenario, Step, TestCase, TestContext};
+use ast::{Feature, Scenario, Step, StepArg, TestCase, TestContext};
 use itertools;
+use std::collections::VecDeque;
+use std::error;
+use std::fmt;
+use std::io::BufRead;
+use std::marker::PhantomData;
 
 use combine::ParseError;
 use combine::Parser;
 use combine::Stream;
 
 use combine::char::{newline, string};
-use combine::{many, many1, optional, sep_by, token};
-use parse_utils::{eol, line_block, until_eol};
+use combine::easy::{self, Error};
+use combine::stream::state::{SourcePosition, State};
+use combine::{choice, many, many1, optional, sep_by, token, try};
+use parse_utils::{doc_string, eol, line_block, table, until_eol};
+use propspec::{NumberLessThan, PropContext, PropScenario, PropStep, StepAsProp, DEFAULT_ITERATIONS, DEFAULT_SEED};
+
+/// A human-readable parse failure for a `.feature` file, carrying the 1-based
+/// line and column of the offending position and the list of tokens the parser
+/// expected there. Unlike the raw combine `easy::Errors`, its `Display` speaks
+/// in terms of the Gherkin keywords an author actually writes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: Vec<String>,
+    /// The offending source line, retained so `Display` can render it with a
+    /// caret underneath the failing column.
+    pub line_text: Option<String>,
+}
+
+impl FeatureParseError {
+    fn from_easy(
+        errors: easy::Errors<char, &str, SourcePosition>,
+        input: &str,
+    ) -> FeatureParseError {
+        let expected = errors
+            .errors
+            .iter()
+            .filter_map(|e| match *e {
+                Error::Expected(ref info) => Some(format!("{}", info)),
+                _ => None,
+            })
+            .collect();
+
+        let line = errors.position.line as usize;
+        let line_text = input.lines().nth(line.saturating_sub(1)).map(|s| s.to_string());
+
+        FeatureParseError {
+            line,
+            column: errors.position.column as usize,
+            expected,
+            line_text,
+        }
+    }
+
+    /// Render the comma-separated list of expected tokens, e.g.
+    /// `expected "Given ", "When ", or "Then "`.
+    fn write_expected(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.expected.split_last() {
+            None => write!(f, "unexpected input"),
+            Some((last, [])) => write!(f, "expected {:?}", last),
+            Some((last, init)) => {
+                let init: Vec<String> = init.iter().map(|e| format!("{:?}", e)).collect();
+                write!(f, "expected {}, or {:?}", init.join(", "), last)
+            }
+        }
+    }
+}
+
+impl fmt::Display for FeatureParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        self.write_expected(f)?;
+
+        // An Ariadne-style snippet: the offending line with a caret pointing at
+        // the failing column, aligned under a line-number gutter.
+        if let Some(ref text) = self.line_text {
+            let gutter = format!("{}", self.line);
+            let pad = " ".repeat(gutter.len());
+            writeln!(f)?;
+            writeln!(f, "{} |", pad)?;
+            writeln!(f, "{} | {}", gutter, text)?;
+            let caret = " ".repeat(self.column.saturating_sub(1));
+            write!(f, "{} | {}^", pad, caret)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for FeatureParseError {}
+
+/// Parse a whole feature file, returning a `FeatureParseError` with source
+/// positions instead of panicking on malformed input. This is the entry point
+/// callers should prefer over `feature(..).easy_parse(..).unwrap()`.
+pub fn parse_feature<'a, C, GP, WP, TP>(
+    given: GP,
+    when: WP,
+    then: TP,
+    input: &'a str,
+) -> Result<Feature<C>, FeatureParseError>
+where
+    C: PropContext + 'static,
+    for<'b> GP: Parser<Input = State<&'b str>, Output = BoxedStep<C>> + Clone,
+    for<'b> WP: Parser<Input = State<&'b str>, Output = BoxedStep<C>> + Clone,
+    for<'b> TP: Parser<Input = State<&'b str>, Output = BoxedStep<C>> + Clone,
+{
+    feature(given, when, then)
+        .easy_parse(State::new(input))
+        .map(|(feat, _rest)| feat)
+        .map_err(|errors| FeatureParseError::from_easy(errors, input))
+}
+
+/// A structured record of a block that failed to parse during resilient
+/// parsing. Rather than aborting the whole feature, the offending region is
+/// skipped and captured here so a runner can report it alongside the scenarios
+/// that did parse.
+#[derive(Debug, Clone)]
+pub struct BlockError {
+    /// 1-based line where the skipped block started.
+    pub line: usize,
+    /// The verbatim text of the skipped block.
+    pub text: String,
+    /// A short description of why it was skipped.
+    pub message: String,
+}
 
 pub struct BoxedStep<C: TestContext> {
     pub val: Box<Step<C>>,
 }
 
+/// Substitute every `<column>` placeholder in an outline step template with the
+/// matching cell from a data row, pairing columns positionally with the header.
+fn substitute(template: &str, header: &[String], row: &[String]) -> String {
+    let mut out = template.to_string();
+    for (name, value) in header.iter().zip(row.iter()) {
+        out = out.replace(&format!("<{}>", name), value);
+    }
+    out
+}
+
+/// Parse an optional line of `@tag` labels (e.g. `@smoke @wip`) that may precede
+/// a `Feature:` or `Scenario:` line. The returned names have their leading `@`
+/// stripped; an absent tag line yields an empty vector.
+fn tags<I>() -> impl Parser<Input = I, Output = Vec<String>>
+where
+    I: Stream<Item = char>,
+    I::Error: ParseError<I::Item, I::Range, I::Position>,
+{
+    use combine::{none_of, sep_by1};
+
+    let tag = (token('@'), many1::<String, _>(none_of(" \t\r\n".chars()))).map(|(_, s)| s);
+    optional(try(
+        (sep_by1::<Vec<String>, _, _>(tag, token(' ')), eol()).map(|(ts, _)| ts),
+    )).map(|o| o.unwrap_or_else(|| vec![]))
+}
+
+/// Parse the optional doc-string or table block that may follow a step line.
+/// When the next line opens a new step instead, the step carries
+/// `StepArg::None`.
+fn step_arg<I>() -> impl Parser<Input = I, Output = StepArg>
+where
+    I: Stream<Item = char>,
+    I::Error: ParseError<I::Item, I::Range, I::Position>,
+{
+    optional(try(doc_string().map(StepArg::DocString)).or(table().map(StepArg::Table)))
+        .map(|o| o.unwrap_or(StepArg::None))
+}
+
 fn scenario_block<I, TC, P>(
     prefix: &'static str,
     inner: P,
-) -> impl Parser<Input = I, Output = Vec<BoxedStep<TC>>>
+) -> impl Parser<Input = I, Output = Vec<(BoxedStep<TC>, StepArg)>>
 where
     TC: TestContext + 'static,
     P: Parser<Input = I, Output = BoxedStep<TC>> + Clone,
     I: Stream<Item = char>,
     I::Error: ParseError<I::Item, I::Range, I::Position>,
 {
-    let first_line = (string(prefix), token(' '), inner.clone(), eol()).map(|t| t.2);
-    let and_line = (string("And "), inner, eol()).map(|t| t.1);
-    (first_line, many(and_line)).map(|(first, mut ands): (BoxedStep<TC>, Vec<BoxedStep<TC>>)| {
-        ands.insert(0, first);
-        ands
-    })
+    let first_line = (string(prefix), token(' '), inner.clone(), eol(), step_arg()).map(|t| (t.2, t.4));
+    let and_line = (string("And "), inner, eol(), step_arg()).map(|t| (t.1, t.3));
+    (first_line, many(and_line)).map(
+        |(first, mut ands): ((BoxedStep<TC>, StepArg), Vec<(BoxedStep<TC>, StepArg)>)| {
+            ands.insert(0, first);
+            ands
+        },
+    )
 }
 
 fn scenario<I, C, GP, WP, TP>(
@@ -57,35 +216,285 @@ where
 
     struct_parser! {
         Scenario {
+            // Look for the keyword before consuming a tag line, so a tagged block
+            // of another kind (e.g. a `Scenario` where a `Background` is optional)
+            // leaves the input untouched.
+            tags: try(::combine::look_ahead((tags(), string(prefix)))).with(tags()),
             _: string(prefix),
             _: string(":"),
             name: choice!(
                 until_eol().map(|s| Some(s.trim().to_string())),
                 newline().map(|_| None)
             ),
-            steps: steps.map(|x| x.into_iter().map(|s| s.val).collect()),
+            steps: steps.map(|x| x.into_iter().map(|(s, arg)| (s.val, arg)).collect()),
         }
     }
 }
 
-/// Construct a feature file parser, built around step parsers
-///
-/// # Arguments
+/// Parse a single `Scenario:` block, exposed for the interactive runner which
+/// accumulates one block at a time from its input and needs to evaluate it in
+/// isolation rather than as part of a whole `Feature`.
+pub fn scenario_parser<I, C, GP, WP, TP>(
+    given: GP,
+    when: WP,
+    then: TP,
+) -> impl Parser<Input = I, Output = Scenario<C>>
+where
+    C: TestContext + 'static,
+    GP: Parser<Input = I, Output = BoxedStep<C>> + Clone,
+    WP: Parser<Input = I, Output = BoxedStep<C>> + Clone,
+    TP: Parser<Input = I, Output = BoxedStep<C>> + Clone,
+    I: Stream<Item = char>,
+    I::Error: ParseError<I::Item, I::Range, I::Position>,
+{
+    scenario("Scenario", given, when, then)
+}
+
+/// Capture a `Given`/`When`/`Then` block as raw step text rather than parsed
+/// `Step`s. Used by `Scenario Outline`, whose step lines contain
+/// `<placeholder>` tokens that cannot be fed to the user's step parsers until
+/// a data row has been substituted in.
+fn raw_block<I>(keyword: &'static str) -> impl Parser<Input = I, Output = Vec<String>>
+where
+    I: Stream<Item = char>,
+    I::Error: ParseError<I::Item, I::Range, I::Position>,
+{
+    let first_line = (string(keyword), token(' '), until_eol()).map(|t| t.2);
+    let and_line = (string("And "), until_eol()).map(|t| t.1);
+    (first_line, many(and_line)).map(|(first, mut ands): (String, Vec<String>)| {
+        ands.insert(0, first);
+        ands
+    })
+}
+
+/// Build the concrete steps for one data row by substituting the row's cells
+/// into each template line and feeding the result back through the user's step
+/// parser. The step parser is re-run over the substituted line as a fresh
+/// `&str` stream, so it must be instantiable for any input lifetime. A line the
+/// parser rejects is returned as an `Err` so the caller can report it as a parse
+/// error rather than panicking mid-expansion.
+fn expand_steps<C, P>(
+    parser: &P,
+    templates: &[String],
+    header: &[String],
+    row: &[String],
+) -> Result<Vec<(Box<Step<C>>, StepArg)>, String>
+where
+    C: TestContext + 'static,
+    for<'a> P: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+{
+    let mut steps = vec![];
+    for t in templates {
+        let line = substitute(t, header, row);
+        match parser.clone().easy_parse(State::new(line.as_str())) {
+            Ok((s, _)) => steps.push((s.val, StepArg::None)),
+            Err(_) => return Err(line),
+        }
+    }
+    Ok(steps)
+}
+
+/// Parse a `Scenario Outline:` block and expand it into one concrete `Scenario`
+/// per row of its `Examples:` table. Each `<column>` token in a step line is
+/// replaced with the matching cell before the step text is handed to the user's
+/// step parsers, so the `Step` trait itself is untouched. Expanded scenarios are
+/// named `"<outline name> [row N]"` (one-based over the data rows) so a failing
+/// example is identifiable from its `TestResult::test_case_name`.
 ///
-/// * `given`, `when`, `then` : User-defined parsers to parse and produce
-/// `Step`s out of the text after `Given`, `When`, and `Then`, respectively.
-pub fn feature<I, C, GP, WP, TP>(
+/// Outlines are expanded here, at parse time, into ordinary `TestCase::Scenario`
+/// values rather than carried as a distinct `TestCase::Outline` expanded at eval
+/// time: the substituted steps are indistinguishable from hand-written scenarios
+/// once built, so a separate variant and eval-time path would only duplicate this
+/// expansion. Column-count and per-row parse failures surface as parse errors,
+/// keeping the whole table's validity a parse-time property.
+fn scenario_outline<I, C, GP, WP, TP>(
     given: GP,
     when: WP,
     then: TP,
-) -> impl Parser<Input = I, Output = Feature<C>>
+) -> impl Parser<Input = I, Output = Vec<TestCase<C>>>
 where
     C: TestContext + 'static,
+    for<'a> GP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+    for<'a> WP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+    for<'a> TP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+    I: Stream<Item = char>,
+    I::Error: ParseError<I::Item, I::Range, I::Position>,
+{
+    let name = (
+        tags(),
+        string("Scenario Outline:"),
+        until_eol().map(|s| s.trim().to_string()),
+    ).map(|t| (t.0, t.2));
+
+    let givens = optional(raw_block("Given")).map(|o| o.unwrap_or_else(|| vec![]));
+    let whens = optional(raw_block("When")).map(|o| o.unwrap_or_else(|| vec![]));
+    let thens = optional(raw_block("Then")).map(|o| o.unwrap_or_else(|| vec![]));
+
+    let examples = (string("Examples:"), eol(), table()).and_then(
+        |(_, _, rows): (_, _, Vec<Vec<String>>)| {
+            if rows.len() < 2 {
+                return Err(Error::Unexpected(
+                    "an Examples table with a header row and at least one data row".into(),
+                ));
+            }
+            let width = rows[0].len();
+            if rows[1..].iter().any(|r| r.len() != width) {
+                return Err(Error::Unexpected(
+                    "Examples data rows whose column count matches the header".into(),
+                ));
+            }
+            Ok(rows)
+        },
+    );
+
+    (name, givens, whens, thens, examples).and_then(
+        move |((tags, name), graw, wraw, traw, rows): (
+            (Vec<String>, String),
+            Vec<String>,
+            Vec<String>,
+            Vec<String>,
+            Vec<Vec<String>>,
+        )| {
+            let header = &rows[0];
+            let mut cases = vec![];
+            for (i, row) in rows[1..].iter().enumerate() {
+                // A cell whose substituted step line the user's parser rejects
+                // is a malformed example, reported as a parse error rather than
+                // a panic.
+                let bad = |line: String| {
+                    Error::Unexpected(
+                        format!("an Examples row whose substituted steps parse (got {:?})", line)
+                            .into(),
+                    )
+                };
+                let mut steps = expand_steps(&given, &graw, header, row).map_err(&bad)?;
+                steps.extend(expand_steps(&when, &wraw, header, row).map_err(&bad)?);
+                steps.extend(expand_steps(&then, &traw, header, row).map_err(&bad)?);
+                cases.push(TestCase::Scenario(Scenario {
+                    name: Some(format!("{} [row {}]", name, i + 1)),
+                    tags: tags.clone(),
+                    steps,
+                }));
+            }
+            Ok(cases)
+        },
+    )
+}
+
+/// Parse the built-in generator grammar `a number <NAME> less than <N>`,
+/// yielding a generator `PropStep` that draws an integer below `N` and stashes
+/// it in the context under `NAME`.
+fn number_generator<I, C>() -> impl Parser<Input = I, Output = Box<PropStep<C>>>
+where
+    C: PropContext + 'static,
+    I: Stream<Item = char>,
+    I::Error: ParseError<I::Item, I::Range, I::Position>,
+{
+    use combine::char::digit;
+    use combine::satisfy;
+
+    let name = many1::<String, _>(satisfy(|c: char| c.is_alphanumeric() || c == '_'));
+    let bound = many1::<String, _>(digit());
+    (string("a number "), name, string(" less than "), bound).map(
+        |(_, name, _, bound): (_, String, _, String)| {
+            Box::new(NumberLessThan {
+                name,
+                bound: bound.parse().unwrap(),
+            }) as Box<PropStep<C>>
+        },
+    )
+}
+
+/// Parse one step of a `PropSpec`: either the built-in number generator or, on
+/// fallback, one of the user's ordinary step definitions wrapped so it can run
+/// in a property scenario.
+fn prop_step<I, C, P>(inner: P) -> impl Parser<Input = I, Output = Box<PropStep<C>>>
+where
+    C: PropContext + 'static,
+    P: Parser<Input = I, Output = BoxedStep<C>> + Clone,
+    I: Stream<Item = char>,
+    I::Error: ParseError<I::Item, I::Range, I::Position>,
+{
+    try(number_generator::<I, C>())
+        .or(inner.map(|b: BoxedStep<C>| Box::new(StepAsProp { step: b.val }) as Box<PropStep<C>>))
+}
+
+fn propspec_block<I, C, P>(
+    prefix: &'static str,
+    inner: P,
+) -> impl Parser<Input = I, Output = Vec<Box<PropStep<C>>>>
+where
+    C: PropContext + 'static,
+    P: Parser<Input = I, Output = BoxedStep<C>> + Clone,
+    I: Stream<Item = char>,
+    I::Error: ParseError<I::Item, I::Range, I::Position>,
+{
+    let first_line = (string(prefix), token(' '), prop_step(inner.clone()), eol()).map(|t| t.2);
+    let and_line = (string("And "), prop_step(inner), eol()).map(|t| t.1);
+    (first_line, many(and_line)).map(
+        |(first, mut ands): (Box<PropStep<C>>, Vec<Box<PropStep<C>>>)| {
+            ands.insert(0, first);
+            ands
+        },
+    )
+}
+
+/// Parse a `PropSpec:` block into a `PropScenario`. Generator steps such as
+/// `a number A less than 10000` draw randomized inputs; the remaining steps are
+/// the user's own definitions. The scenario runs `DEFAULT_ITERATIONS` times from
+/// the reproducible `DEFAULT_SEED` and shrinks any failure to a minimal case.
+fn propspec<I, C, GP, WP, TP>(
+    given: GP,
+    when: WP,
+    then: TP,
+) -> impl Parser<Input = I, Output = PropScenario<C>>
+where
+    C: PropContext + 'static,
     GP: Parser<Input = I, Output = BoxedStep<C>> + Clone,
     WP: Parser<Input = I, Output = BoxedStep<C>> + Clone,
     TP: Parser<Input = I, Output = BoxedStep<C>> + Clone,
     I: Stream<Item = char>,
     I::Error: ParseError<I::Item, I::Range, I::Position>,
+{
+    let name = (
+        string("PropSpec:"),
+        until_eol().map(|s| s.trim().to_string()),
+    ).map(|t| t.1);
+
+    let givens = propspec_block("Given", given);
+    let whens = propspec_block("When", when);
+    let thens = propspec_block("Then", then);
+
+    let steps = (
+        optional(givens).map(|o| o.unwrap_or_else(|| vec![])),
+        optional(whens).map(|o| o.unwrap_or_else(|| vec![])),
+        optional(thens).map(|o| o.unwrap_or_else(|| vec![])),
+    ).map(|(g, w, t)| itertools::concat(vec![g, w, t]));
+
+    (name, steps).map(|(name, steps): (String, Vec<Box<PropStep<C>>>)| PropScenario {
+        name,
+        steps,
+        iterations: DEFAULT_ITERATIONS,
+        seed: DEFAULT_SEED,
+    })
+}
+
+/// Construct a feature file parser, built around step parsers
+///
+/// # Arguments
+///
+/// * `given`, `when`, `then` : User-defined parsers to parse and produce
+/// `Step`s out of the text after `Given`, `When`, and `Then`, respectively.
+pub fn feature<'a, C, GP, WP, TP>(
+    given: GP,
+    when: WP,
+    then: TP,
+) -> impl Parser<Input = State<&'a str>, Output = Feature<C>>
+where
+    C: PropContext + 'static,
+    for<'b> GP: Parser<Input = State<&'b str>, Output = BoxedStep<C>> + Clone,
+    for<'b> WP: Parser<Input = State<&'b str>, Output = BoxedStep<C>> + Clone,
+    for<'b> TP: Parser<Input = State<&'b str>, Output = BoxedStep<C>> + Clone,
 {
     let blank_lines = || many1::<Vec<_>, _>(newline());
 
@@ -96,13 +505,21 @@ where
         ).map(|t| TestCase::Background(t.0))
     );
 
-    let test_cases = sep_by(
-        scenario("Scenario", given, when, then).map(|s| TestCase::Scenario(s)),
-        blank_lines());
+    // A block is a `PropSpec` (randomized scenario), a `Scenario Outline` (which
+    // expands into several test cases), or a plain `Scenario` (a single one).
+    // The outline is tried before the plain scenario because both share the
+    // `Scenario` prefix.
+    let prop = propspec(given.clone(), when.clone(), then.clone())
+        .map(|p| vec![TestCase::PropSpec(p)]);
+    let outline = scenario_outline(given.clone(), when.clone(), then.clone());
+    let plain = scenario("Scenario", given, when, then).map(|s| vec![TestCase::Scenario(s)]);
+    let test_cases = sep_by(try(prop).or(try(outline)).or(plain), blank_lines())
+        .map(|blocks: Vec<Vec<TestCase<C>>>| itertools::concat(blocks));
 
     struct_parser! {
         Feature {
             _: optional(blank_lines()),
+            tags: tags(),
             _: string("Feature: "),
             name: until_eol(),
             comment: line_block(),
@@ -113,6 +530,350 @@ where
     }
 }
 
+/// Parse a feature in resilient mode: when a `Background`/`Scenario` block fails
+/// to parse, the block is skipped up to the next blank-line boundary (the same
+/// separator `feature` uses between blocks), a `BlockError` is recorded, and
+/// parsing continues with the remaining blocks. The returned `Feature` contains
+/// every block that *did* parse, so a runner can still execute and report them.
+///
+/// Only the feature header itself (the `Feature:` line) is non-recoverable; a
+/// malformed header yields a `FeatureParseError`.
+pub fn parse_feature_resilient<'a, C, GP, WP, TP>(
+    given: GP,
+    when: WP,
+    then: TP,
+    input: &'a str,
+) -> Result<(Feature<C>, Vec<BlockError>), FeatureParseError>
+where
+    C: PropContext + 'static,
+    for<'b> GP: Parser<Input = State<&'b str>, Output = BoxedStep<C>> + Clone,
+    for<'b> WP: Parser<Input = State<&'b str>, Output = BoxedStep<C>> + Clone,
+    for<'b> TP: Parser<Input = State<&'b str>, Output = BoxedStep<C>> + Clone,
+{
+    let blank_lines = || many1::<Vec<_>, _>(newline());
+    let mut header = (
+        optional(blank_lines()),
+        tags(),
+        string("Feature: "),
+        until_eol(),
+        line_block(),
+        blank_lines(),
+    ).map(|t| (t.1, t.3, t.4));
+
+    let (feature_tags, name, comment, rest) = header
+        .easy_parse(State::new(input))
+        .map(|((tags, name, comment), rest)| (tags, name, comment, rest.input))
+        .map_err(|errors| FeatureParseError::from_easy(errors, input))?;
+
+    // Line offset of `rest` within the original input, for error reporting.
+    let consumed = input.len() - rest.len();
+    let base = input[..consumed].matches('\n').count();
+
+    let mut background = None;
+    let mut test_cases = vec![];
+    let mut errors = vec![];
+
+    for (rel_line, raw) in fence_aware_blocks(rest) {
+        let block = raw.trim_matches('\n');
+        let start_line = base + rel_line;
+
+        if block.trim().is_empty() {
+            continue;
+        }
+
+        let text = format!("{}\n", block);
+
+        // Classify on the first non-tag line so a tagged block is still routed
+        // to the right parser.
+        let keyword_line = block
+            .lines()
+            .find(|l| !l.trim_start().starts_with('@'))
+            .unwrap_or("");
+
+        if keyword_line.starts_with("Background:") {
+            match scenario("Background", given.clone(), when.clone(), then.clone())
+                .easy_parse(State::new(text.as_str()))
+            {
+                Ok((s, _)) => background = Some(TestCase::Background(s)),
+                Err(e) => errors.push(block_error(start_line, block, &e)),
+            }
+        } else if keyword_line.starts_with("Scenario Outline:") {
+            match scenario_outline(given.clone(), when.clone(), then.clone())
+                .easy_parse(State::new(text.as_str()))
+            {
+                Ok((cases, _)) => test_cases.extend(cases),
+                Err(e) => errors.push(block_error(start_line, block, &e)),
+            }
+        } else if keyword_line.starts_with("PropSpec:") {
+            match propspec(given.clone(), when.clone(), then.clone())
+                .easy_parse(State::new(text.as_str()))
+            {
+                Ok((p, _)) => test_cases.push(TestCase::PropSpec(p)),
+                Err(e) => errors.push(block_error(start_line, block, &e)),
+            }
+        } else {
+            match scenario_parser(given.clone(), when.clone(), then.clone())
+                .easy_parse(State::new(text.as_str()))
+            {
+                Ok((s, _)) => test_cases.push(TestCase::Scenario(s)),
+                Err(e) => errors.push(block_error(start_line, block, &e)),
+            }
+        }
+    }
+
+    let feature = Feature {
+        name,
+        comment,
+        tags: feature_tags,
+        background,
+        test_cases,
+    };
+
+    Ok((feature, errors))
+}
+
+/// An iterator that parses a feature file incrementally from a reader, yielding
+/// each `TestCase` as soon as its block (terminated by a blank line) has been
+/// read, rather than buffering the whole file. A `Scenario Outline` block is
+/// expanded on arrival, so its rows are yielded one after another. Created by
+/// [`features_from_reader`].
+pub struct FeatureReader<R, C, GP, WP, TP> {
+    lines: ::std::io::Lines<R>,
+    given: GP,
+    when: WP,
+    then: TP,
+    header_seen: bool,
+    done: bool,
+    pending: VecDeque<TestCase<C>>,
+    _marker: PhantomData<C>,
+}
+
+/// Parse a `Feature` incrementally from `reader`, returning an iterator that
+/// yields each `TestCase` as its block completes. This lets a runner begin
+/// executing early scenarios while later ones are still being read, and keeps
+/// memory flat for large or piped suites.
+pub fn features_from_reader<R, C, GP, WP, TP>(
+    reader: R,
+    given: GP,
+    when: WP,
+    then: TP,
+) -> FeatureReader<R, C, GP, WP, TP>
+where
+    R: BufRead,
+    C: PropContext + 'static,
+    for<'a> GP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+    for<'a> WP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+    for<'a> TP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+{
+    FeatureReader {
+        lines: reader.lines(),
+        given,
+        when,
+        then,
+        header_seen: false,
+        done: false,
+        pending: VecDeque::new(),
+        _marker: PhantomData,
+    }
+}
+
+impl<R, C, GP, WP, TP> FeatureReader<R, C, GP, WP, TP>
+where
+    R: BufRead,
+    C: PropContext + 'static,
+    for<'a> GP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+    for<'a> WP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+    for<'a> TP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+{
+    /// Read lines until a blank line or end of input closes off a block.
+    /// Returns the accumulated block, or `None` once the stream is exhausted.
+    fn next_block(&mut self) -> Option<Result<String, FeatureParseError>> {
+        let mut block = String::new();
+        // A blank line only closes a block when we are not inside a `"""`
+        // doc-string fence, whose interior may legitimately contain blanks.
+        let mut in_doc = false;
+        loop {
+            match self.lines.next() {
+                Some(Ok(line)) => {
+                    if line.trim() == "\"\"\"" {
+                        in_doc = !in_doc;
+                    }
+                    if line.trim().is_empty() && !in_doc {
+                        if block.trim().is_empty() {
+                            continue;
+                        }
+                        return Some(Ok(block));
+                    }
+                    block.push_str(&line);
+                    block.push('\n');
+                }
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(FeatureParseError {
+                        line: 0,
+                        column: 0,
+                        expected: vec![format!("{}", e)],
+                        line_text: None,
+                    }));
+                }
+                None => {
+                    self.done = true;
+                    if block.trim().is_empty() {
+                        return None;
+                    }
+                    return Some(Ok(block));
+                }
+            }
+        }
+    }
+
+    /// Parse one non-header block into the test cases it produces (an outline
+    /// yields several).
+    fn parse_block(&self, block: &str) -> Result<Vec<TestCase<C>>, FeatureParseError> {
+        let text = format!("{}\n", block.trim_matches('\n'));
+        let keyword_line = block
+            .lines()
+            .find(|l| !l.trim_start().starts_with('@'))
+            .unwrap_or("");
+
+        let from_easy = |errors| FeatureParseError::from_easy(errors, block);
+
+        if keyword_line.starts_with("Background:") {
+            scenario("Background", self.given.clone(), self.when.clone(), self.then.clone())
+                .easy_parse(State::new(text.as_str()))
+                .map(|(s, _)| vec![TestCase::Background(s)])
+                .map_err(from_easy)
+        } else if keyword_line.starts_with("Scenario Outline:") {
+            scenario_outline(self.given.clone(), self.when.clone(), self.then.clone())
+                .easy_parse(State::new(text.as_str()))
+                .map(|(cases, _)| cases)
+                .map_err(from_easy)
+        } else if keyword_line.starts_with("PropSpec:") {
+            propspec(self.given.clone(), self.when.clone(), self.then.clone())
+                .easy_parse(State::new(text.as_str()))
+                .map(|(p, _)| vec![TestCase::PropSpec(p)])
+                .map_err(from_easy)
+        } else {
+            scenario_parser(self.given.clone(), self.when.clone(), self.then.clone())
+                .easy_parse(State::new(text.as_str()))
+                .map(|(s, _)| vec![TestCase::Scenario(s)])
+                .map_err(from_easy)
+        }
+    }
+}
+
+impl<R, C, GP, WP, TP> Iterator for FeatureReader<R, C, GP, WP, TP>
+where
+    R: BufRead,
+    C: PropContext + 'static,
+    for<'a> GP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+    for<'a> WP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+    for<'a> TP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+{
+    type Item = Result<TestCase<C>, FeatureParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(tc) = self.pending.pop_front() {
+                return Some(Ok(tc));
+            }
+            if self.done {
+                return None;
+            }
+
+            let block = match self.next_block() {
+                Some(Ok(b)) => b,
+                Some(Err(e)) => return Some(Err(e)),
+                None => return None,
+            };
+
+            // The first block is the `Feature:` header; consume and discard it.
+            if !self.header_seen {
+                self.header_seen = true;
+                continue;
+            }
+
+            match self.parse_block(&block) {
+                Ok(cases) => self.pending.extend(cases),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Split `text` into blank-line-separated blocks, returning each block's 1-based
+/// start line alongside its text. A blank line inside a `"""` doc-string fence
+/// is treated as block content rather than a boundary, so a step carrying a
+/// multi-line doc string is not split mid-argument.
+fn fence_aware_blocks(text: &str) -> Vec<(usize, String)> {
+    let mut blocks = vec![];
+    let mut current = String::new();
+    let mut start_line = 1;
+    let mut line_no = 0;
+    let mut in_doc = false;
+
+    for line in text.lines() {
+        line_no += 1;
+        if line.trim() == "\"\"\"" {
+            in_doc = !in_doc;
+        }
+        if line.trim().is_empty() && !in_doc {
+            if !current.trim().is_empty() {
+                blocks.push((start_line, current.clone()));
+            }
+            current.clear();
+            start_line = line_no + 1;
+        } else {
+            if current.is_empty() {
+                start_line = line_no;
+            }
+            current.push_str(line);
+            current.push('\n');
+        }
+    }
+
+    if !current.trim().is_empty() {
+        blocks.push((start_line, current));
+    }
+
+    blocks
+}
+
+fn block_error(
+    line: usize,
+    block: &str,
+    errors: &easy::Errors<char, &str, SourcePosition>,
+) -> BlockError {
+    BlockError {
+        line,
+        text: block.to_string(),
+        message: format!("parse failed near column {}", errors.position.column),
+    }
+}
+
+/// Construct a feature file parser from a *registry* of step definitions per
+/// keyword rather than a single parser each. Each keyword is handed a
+/// `Vec<P>` of alternative step parsers; at parse time the first one that
+/// matches a given step line wins, as `combine::choice` dispatches over the
+/// vector. This lets callers register many step shapes per keyword and add new
+/// definitions without hand-combining one giant `choice!`.
+///
+/// # Arguments
+///
+/// * `given`, `when`, `then` : Vectors of user-defined step parsers tried in
+/// order for the text after `Given`, `When`, and `Then`, respectively.
+pub fn feature_with_registry<'a, C, P>(
+    given: Vec<P>,
+    when: Vec<P>,
+    then: Vec<P>,
+) -> impl Parser<Input = State<&'a str>, Output = Feature<C>>
+where
+    C: PropContext + 'static,
+    for<'b> P: Parser<Input = State<&'b str>, Output = BoxedStep<C>> + Clone,
+{
+    feature(choice(given), choice(when), choice(then))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,12 +892,16 @@ mod tests {
         }
     }
 
+    impl PropContext for SampleTestContext {
+        fn set_number(&mut self, _name: &str, _value: i64) {}
+    }
+
     struct SampleStep {
         num: u32,
     }
 
     impl Step<SampleTestContext> for SampleStep {
-        fn eval(&self, context: &mut SampleTestContext) -> bool {
+        fn eval(&self, context: &mut SampleTestContext, _arg: &StepArg) -> bool {
             context.executed_steps.push(self.num);
             true
         }
@@ -163,6 +928,27 @@ mod tests {
         feat
     }
 
+    /// Like `do_parse`, but surfaces the parse `Result` so tests can assert on
+    /// failures (e.g. malformed `Examples:` tables) instead of panicking.
+    fn do_parse_result(
+        s: &str,
+    ) -> Result<Feature<SampleTestContext>, easy::Errors<char, &str, SourcePosition>> {
+        use combine::char::digit;
+        use combine::token;
+
+        let num_digit = || digit().map(|c| c.to_digit(10).unwrap());
+        let given = struct_parser! { SampleStep { _: token('G'), num: num_digit() } };
+        let when = struct_parser! { SampleStep { _: token('W'), num: num_digit() } };
+        let then = struct_parser! { SampleStep { _: token('T'), num: num_digit() } };
+
+        feature(
+            given.map(|x| BoxedStep { val: Box::new(x) }),
+            when.map(|x| BoxedStep { val: Box::new(x) }),
+            then.map(|x| BoxedStep { val: Box::new(x) }),
+        ).easy_parse(State::new(s))
+            .map(|(feat, _rest)| feat)
+    }
+
     #[test]
     fn test_parse() {
         let feat = do_parse(
@@ -205,6 +991,46 @@ And T7");
         assert_eq!(results[1].context.executed_steps, [1, 2, 3, 4, 5, 6, 7]);
     }
 
+    #[test]
+    fn test_parse_step_arguments() {
+        let feat = do_parse(
+            "
+Feature: f
+
+Scenario: args
+Given G1
+\"\"\"
+hello
+world
+\"\"\"
+When W2
+| a | b |
+| 1 | 2 |
+Then T3",
+        );
+
+        let steps = match feat.test_cases[0] {
+            TestCase::Scenario(ref s) => &s.steps,
+            _ => panic!("expected a plain scenario"),
+        };
+
+        match steps[0].1 {
+            StepArg::DocString(ref d) => assert_eq!(d, "hello\nworld"),
+            _ => panic!("first step should carry a doc string"),
+        }
+        match steps[1].1 {
+            StepArg::Table(ref rows) => {
+                assert_eq!(rows[0][0], "a");
+                assert_eq!(rows[1][1], "2");
+            }
+            _ => panic!("second step should carry a table"),
+        }
+        match steps[2].1 {
+            StepArg::None => {}
+            _ => panic!("third step should carry no argument"),
+        }
+    }
+
     #[test]
     fn test_parse_extra_whitespace() {
         let feat = do_parse(
@@ -241,4 +1067,328 @@ Then T2
         assert_eq!(results[1].test_case_name, "Two".to_string());
         assert_eq!(results[1].context.executed_steps, [2, 2, 2]);
     }
+
+    #[test]
+    fn test_parse_outline() {
+        let feat = do_parse(
+            r"
+Feature: my feature
+
+Scenario Outline: counting
+Given G<n>
+When W<n>
+Then T<n>
+Examples:
+| n |
+| 1 |
+| 2 |");
+
+        assert_eq!(feat.test_cases.len(), 2);
+        assert_eq!(
+            feat.test_cases[0].name(),
+            Some("counting [row 1]".to_string())
+        );
+        assert_eq!(
+            feat.test_cases[1].name(),
+            Some("counting [row 2]".to_string())
+        );
+
+        let results = feat.eval();
+
+        assert_eq!(results[0].context.executed_steps, [1, 1, 1]);
+        assert_eq!(results[1].context.executed_steps, [2, 2, 2]);
+    }
+
+    #[test]
+    fn test_substitute_repeated_placeholder() {
+        let header = vec!["n".to_string()];
+        let row = vec!["7".to_string()];
+        assert_eq!(substitute("<n> and <n>", &header, &row), "7 and 7");
+    }
+
+    #[test]
+    fn test_outline_header_column_count() {
+        // With the framed-cell fix, a two-column Examples table yields exactly
+        // two cells per row — no phantom trailing column.
+        let (rows, _) = table().parse("| a | b |\n| 1 | 2 |\n").unwrap();
+        assert_eq!(rows[0].len(), 2);
+        assert_eq!(rows[0], vec!["a".to_string(), "b".to_string()]);
+
+        // A two-placeholder outline expands cleanly over that header.
+        let feat = do_parse(
+            r"
+Feature: f
+
+Scenario Outline: add
+Given G<a>
+When W<b>
+Examples:
+| a | b |
+| 1 | 2 |",
+        );
+        assert_eq!(feat.test_cases.len(), 1);
+        let results = feat.eval();
+        assert_eq!(results[0].context.executed_steps, [1, 2]);
+    }
+
+    #[test]
+    fn test_outline_column_mismatch_is_error() {
+        let result = do_parse_result(
+            r"
+Feature: f
+
+Scenario Outline: o
+Given G<n>
+Examples:
+| n | m |
+| 1 |",
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_outline_empty_table_is_error() {
+        let result = do_parse_result(
+            r"
+Feature: f
+
+Scenario Outline: o
+Given G<n>
+Examples:
+| n |",
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_outline_unparseable_row_is_error() {
+        // `Gx` is not a valid step (the parser wants a digit after `G`), so the
+        // substitution of a bad cell surfaces as a parse error rather than a
+        // panic during expansion.
+        let result = do_parse_result(
+            r"
+Feature: f
+
+Scenario Outline: o
+Given G<n>
+Examples:
+| n |
+| x |",
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_features_from_reader() {
+        use std::io::Cursor;
+        use combine::char::digit;
+        use combine::token;
+
+        let num_digit = || digit().map(|c| c.to_digit(10).unwrap());
+        let given = struct_parser! { SampleStep { _: token('G'), num: num_digit() } };
+        let when = struct_parser! { SampleStep { _: token('W'), num: num_digit() } };
+        let then = struct_parser! { SampleStep { _: token('T'), num: num_digit() } };
+
+        let src = "Feature: f\n\nScenario: a\nGiven G1\n\nScenario: b\nGiven G2\n";
+        let cases: Vec<_> = features_from_reader::<_, SampleTestContext, _, _, _>(
+            Cursor::new(src),
+            given.map(|x| BoxedStep { val: Box::new(x) }),
+            when.map(|x| BoxedStep { val: Box::new(x) }),
+            then.map(|x| BoxedStep { val: Box::new(x) }),
+        ).collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[0].name(), Some("a".to_string()));
+        assert_eq!(cases[1].name(), Some("b".to_string()));
+    }
+
+    #[test]
+    fn test_features_from_reader_multiline_doc_string() {
+        use std::io::Cursor;
+        use combine::char::digit;
+        use combine::token;
+
+        let num_digit = || digit().map(|c| c.to_digit(10).unwrap());
+        let given = struct_parser! { SampleStep { _: token('G'), num: num_digit() } };
+        let when = struct_parser! { SampleStep { _: token('W'), num: num_digit() } };
+        let then = struct_parser! { SampleStep { _: token('T'), num: num_digit() } };
+
+        // The doc string contains a blank interior line, which must not split
+        // the block under streaming.
+        let src = "Feature: f\n\nScenario: a\nGiven G1\n\"\"\"\nfoo\n\nbar\n\"\"\"\nWhen W2\n";
+        let cases: Vec<_> = features_from_reader::<_, SampleTestContext, _, _, _>(
+            Cursor::new(src),
+            given.map(|x| BoxedStep { val: Box::new(x) }),
+            when.map(|x| BoxedStep { val: Box::new(x) }),
+            then.map(|x| BoxedStep { val: Box::new(x) }),
+        ).collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(cases.len(), 1);
+        let steps = match cases[0] {
+            TestCase::Scenario(ref s) => &s.steps,
+            _ => panic!("expected a plain scenario"),
+        };
+        match steps[0].1 {
+            StepArg::DocString(ref d) => assert_eq!(d, "foo\n\nbar"),
+            _ => panic!("first step should carry the multi-line doc string"),
+        }
+    }
+
+    #[test]
+    fn test_parse_tags_and_filter() {
+        let feat = do_parse(
+            r"
+@regression
+Feature: my feature
+
+@smoke
+Scenario: a
+Given G1
+
+@smoke @wip
+Scenario: b
+Given G2",
+        );
+
+        assert_eq!(feat.tags, vec!["regression".to_string()]);
+
+        // `@smoke and not @wip` selects only the first scenario.
+        let results = feat.eval_filtered("@smoke and not @wip").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].test_case_name, "a".to_string());
+
+        // The feature-level tag is inherited by both scenarios.
+        let all = feat.eval_filtered("@regression").unwrap();
+        assert_eq!(all.len(), 2);
+
+        assert!(feat.eval_filtered("@smoke and").is_err());
+    }
+
+    #[test]
+    fn test_parse_with_registry() {
+        use combine::char::digit;
+        use combine::token;
+
+        let num_digit = || digit().map(|c| c.to_digit(10).unwrap());
+
+        // Two alternative `Given` shapes registered under the same keyword:
+        // `G<n>` as usual, plus `g<n>` as a second definition. Both come from one
+        // constructor so they share a type and fit in a single `Vec`.
+        let given_with = |c: char| {
+            struct_parser! { SampleStep { _: token(c), num: num_digit() } }
+                .map(|x| BoxedStep { val: Box::new(x) })
+        };
+        let given_upper = given_with('G');
+        let given_lower = given_with('g');
+        let when = struct_parser! { SampleStep { _: token('W'), num: num_digit() } }
+            .map(|x| BoxedStep { val: Box::new(x) });
+        let then = struct_parser! { SampleStep { _: token('T'), num: num_digit() } }
+            .map(|x| BoxedStep { val: Box::new(x) });
+
+        let (feat, _remaining) = feature_with_registry(
+            vec![given_upper, given_lower],
+            vec![when],
+            vec![then],
+        ).easy_parse(State::new(
+            r"
+Feature: registry
+
+Scenario: mixed
+Given G1
+And g2
+When W3
+Then T4",
+        )).unwrap();
+
+        let results = feat.eval();
+        assert_eq!(results[0].pass, true);
+        assert_eq!(results[0].context.executed_steps, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_parse_resilient_collects_bad_blocks() {
+        use combine::char::digit;
+        use combine::token;
+
+        let num_digit = || digit().map(|c| c.to_digit(10).unwrap());
+        let given = struct_parser! { SampleStep { _: token('G'), num: num_digit() } };
+        let when = struct_parser! { SampleStep { _: token('W'), num: num_digit() } };
+        let then = struct_parser! { SampleStep { _: token('T'), num: num_digit() } };
+
+        let (feat, errors) = parse_feature_resilient::<SampleTestContext, _, _, _>(
+            given.map(|x| BoxedStep { val: Box::new(x) }),
+            when.map(|x| BoxedStep { val: Box::new(x) }),
+            then.map(|x| BoxedStep { val: Box::new(x) }),
+            "Feature: f
+comment
+
+Scenario: good
+Given G1
+
+Scenario: bad
+Given QX
+
+Scenario: good2
+Given G2
+",
+        ).unwrap();
+
+        // The two well-formed scenarios survive; the malformed one is recorded.
+        assert_eq!(feat.test_cases.len(), 2);
+        assert_eq!(feat.test_cases[0].name(), Some("good".to_string()));
+        assert_eq!(feat.test_cases[1].name(), Some("good2".to_string()));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].text.contains("bad"));
+    }
+
+    #[test]
+    fn test_parse_feature_error() {
+        use combine::char::digit;
+        use combine::token;
+
+        let num_digit = || digit().map(|c| c.to_digit(10).unwrap());
+        let given = struct_parser! { SampleStep { _: token('G'), num: num_digit() } };
+        let when = struct_parser! { SampleStep { _: token('W'), num: num_digit() } };
+        let then = struct_parser! { SampleStep { _: token('T'), num: num_digit() } };
+
+        let err = parse_feature::<SampleTestContext, _, _, _>(
+            given.map(|x| BoxedStep { val: Box::new(x) }),
+            when.map(|x| BoxedStep { val: Box::new(x) }),
+            then.map(|x| BoxedStep { val: Box::new(x) }),
+            "not a feature at all\n",
+        ).unwrap_err();
+
+        assert_eq!(err.line, 1);
+        assert_eq!(err.column, 1);
+
+        // The rendered diagnostic annotates the offending source line with a
+        // caret under the failing column.
+        let rendered = format!("{}", err);
+        assert!(rendered.contains("not a feature at all"));
+        assert!(rendered.contains("^"));
+    }
+
+    #[test]
+    fn test_parse_propspec() {
+        let feat = do_parse(
+            r"
+Feature: f
+
+PropSpec: random draws
+Given a number A less than 10
+And a number B less than 10
+When W1
+Then T2");
+
+        assert_eq!(feat.test_cases.len(), 1);
+        assert_eq!(feat.test_cases[0].name(), Some("random draws".to_string()));
+
+        // The generator steps draw inputs and the ordinary steps always hold, so
+        // the property passes with no counterexample.
+        let results = feat.eval();
+        assert_eq!(results[0].pass, true);
+        assert!(results[0].counterexample.is_none());
+    }
 }
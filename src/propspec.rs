@@ -0,0 +1,292 @@
+//! Property-based test cases. A `PropScenario` runs its steps many times
+//! against randomly generated inputs; when a run fails it is shrunk down to a
+//! minimal counterexample so the reported failure is reproducible and as small
+//! as the property allows.
+
+use ast::{Counterexample, Step, StepArg, TestContext, TestResult};
+
+/// Default number of randomized iterations run for a `PropScenario` when the
+/// caller does not request a specific count.
+pub const DEFAULT_ITERATIONS: usize = 100;
+
+/// Default seed used for `PropSpec` blocks parsed from a feature file, so a run
+/// is reproducible without the author having to specify one.
+pub const DEFAULT_SEED: u64 = 0x5eed;
+
+/// A context that a `PropSpec`'s generator steps can stash drawn numbers into,
+/// keyed by the name used in the feature file (`a number A less than ...` stores
+/// under `"A"`). Later steps read the value back out of the context.
+pub trait PropContext: TestContext {
+    fn set_number(&mut self, name: &str, value: i64);
+}
+
+/// The built-in generator step produced by the `a number <NAME> less than <N>`
+/// grammar: it draws a value in `[0, bound)` and stashes it under `name`.
+pub struct NumberLessThan {
+    pub name: String,
+    pub bound: i64,
+}
+
+impl<C: PropContext> PropStep<C> for NumberLessThan {
+    fn eval(&self, ctx: &mut C, inputs: &mut InputSource) -> bool {
+        let value = inputs.draw(self.bound);
+        ctx.set_number(&self.name, value);
+        true
+    }
+}
+
+/// Adapts an ordinary `Step` into a `PropStep` that ignores the input source, so
+/// a `PropSpec` can mix the user's regular step definitions with generator
+/// steps.
+pub struct StepAsProp<C: TestContext> {
+    pub step: Box<Step<C>>,
+}
+
+impl<C: TestContext> PropStep<C> for StepAsProp<C> {
+    fn eval(&self, ctx: &mut C, _inputs: &mut InputSource) -> bool {
+        self.step.eval(ctx, &StepArg::None)
+    }
+}
+
+/// Source of the integer inputs consumed by a `PropStep`. During generation it
+/// draws fresh values from a seeded RNG and records them; during replay (used
+/// by shrinking) it hands back a fixed sequence so a run can be reproduced with
+/// candidate inputs substituted in.
+pub enum InputSource {
+    Generate { rng: Rng, drawn: Vec<i64> },
+    Replay { values: Vec<i64>, cursor: usize },
+}
+
+impl InputSource {
+    fn generate(seed: u64) -> InputSource {
+        InputSource::Generate {
+            rng: Rng::new(seed),
+            drawn: vec![],
+        }
+    }
+
+    fn replay(values: Vec<i64>) -> InputSource {
+        InputSource::Replay { values, cursor: 0 }
+    }
+
+    /// Draw the next non-negative input below `bound`. Generator steps call this
+    /// and stash the result in the context; the value used is recorded so the
+    /// run can later be replayed and shrunk.
+    pub fn draw(&mut self, bound: i64) -> i64 {
+        match *self {
+            InputSource::Generate { ref mut rng, ref mut drawn } => {
+                let bound = if bound <= 0 { 1 } else { bound };
+                let v = (rng.next() % bound as u64) as i64;
+                drawn.push(v);
+                v
+            }
+            InputSource::Replay { ref values, ref mut cursor } => {
+                let v = values.get(*cursor).cloned().unwrap_or(0);
+                *cursor += 1;
+                v
+            }
+        }
+    }
+
+    fn recorded(self) -> Vec<i64> {
+        match self {
+            InputSource::Generate { drawn, .. } => drawn,
+            InputSource::Replay { values, .. } => values,
+        }
+    }
+}
+
+/// A seedable `xorshift64*` generator. The crate avoids a `rand` dependency so
+/// that generation stays deterministic for a given seed.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // Avoid the zero state, which xorshift cannot leave.
+        Rng { state: seed | 1 }
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+/// A single step of a property scenario. Generator steps draw from `inputs` and
+/// record state in `ctx`; assertion steps leave `inputs` untouched and return
+/// whether the property still holds.
+pub trait PropStep<C: TestContext> {
+    fn eval(&self, ctx: &mut C, inputs: &mut InputSource) -> bool;
+}
+
+/// A randomized scenario: its steps are run `iterations` times, each with a
+/// fresh context and freshly drawn inputs. A run in which any step returns
+/// `false` is a failing counterexample.
+pub struct PropScenario<C: TestContext> {
+    pub name: String,
+    pub steps: Vec<Box<PropStep<C>>>,
+    pub iterations: usize,
+    pub seed: u64,
+}
+
+impl<C: TestContext> PropScenario<C> {
+    /// Run the step sequence once against `inputs`, returning the index of the
+    /// first failing step, or `None` if every step passed.
+    fn run(&self, inputs: &mut InputSource) -> (C, Option<usize>) {
+        let mut ctx = C::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            if !step.eval(&mut ctx, inputs) {
+                return (ctx, Some(i));
+            }
+        }
+        (ctx, None)
+    }
+
+    /// Replay a candidate set of inputs and report whether it still fails and,
+    /// if so, at which step.
+    fn fails_with(&self, values: &[i64]) -> Option<usize> {
+        let mut inputs = InputSource::replay(values.to_vec());
+        self.run(&mut inputs).1
+    }
+
+    /// Shrink a failing set of inputs toward zero. Each round proposes, for each
+    /// position, the smaller candidates `0`, `1`, `value / 2`, and `value - 1`,
+    /// tried in that (ascending) order; the smallest candidate that still fails
+    /// is kept and the search repeats.
+    /// A candidate is only accepted if it still reproduces a failure, so a
+    /// passing case can never be reported as the counterexample.
+    fn shrink(&self, mut values: Vec<i64>) -> (Vec<i64>, usize) {
+        let mut failing_step = self.fails_with(&values).expect("seed case must fail");
+
+        loop {
+            let mut improved = false;
+            for i in 0..values.len() {
+                for &candidate in &[0, 1, values[i] / 2, values[i] - 1] {
+                    if candidate < 0 || candidate >= values[i] {
+                        continue;
+                    }
+                    let mut trial = values.clone();
+                    trial[i] = candidate;
+                    if let Some(step) = self.fails_with(&trial) {
+                        values = trial;
+                        failing_step = step;
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+
+        (values, failing_step)
+    }
+
+    /// Run the property, shrinking the first failure found to a minimal
+    /// counterexample.
+    pub fn eval(&self) -> TestResult<C> {
+        for i in 0..self.iterations {
+            let mut inputs = InputSource::generate(self.seed.wrapping_add(i as u64));
+            let (_ctx, failed) = self.run(&mut inputs);
+            if failed.is_some() {
+                let (minimal, failing_step) = self.shrink(inputs.recorded());
+                let mut replay = InputSource::replay(minimal.clone());
+                let (context, _) = self.run(&mut replay);
+                return TestResult {
+                    test_case_name: self.name.clone(),
+                    pass: false,
+                    context,
+                    counterexample: Some(Counterexample {
+                        inputs: minimal,
+                        failing_step,
+                    }),
+                };
+            }
+        }
+
+        TestResult {
+            test_case_name: self.name.clone(),
+            pass: true,
+            context: C::new(),
+            counterexample: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Ctx {
+        a: i64,
+    }
+
+    impl TestContext for Ctx {
+        fn new() -> Ctx {
+            Ctx { a: 0 }
+        }
+    }
+
+    /// Draws a number in `[0, 10_000)` and stores it in the context.
+    struct DrawA;
+    impl PropStep<Ctx> for DrawA {
+        fn eval(&self, ctx: &mut Ctx, inputs: &mut InputSource) -> bool {
+            ctx.a = inputs.draw(10_000);
+            true
+        }
+    }
+
+    /// A deliberately false property: `a` is claimed to always be below 5.
+    struct AssertBelowFive;
+    impl PropStep<Ctx> for AssertBelowFive {
+        fn eval(&self, ctx: &mut Ctx, _inputs: &mut InputSource) -> bool {
+            ctx.a < 5
+        }
+    }
+
+    #[test]
+    fn shrinks_to_minimal_failing_input() {
+        let prop = PropScenario {
+            name: "a is below five".to_string(),
+            steps: vec![Box::new(DrawA), Box::new(AssertBelowFive)],
+            iterations: DEFAULT_ITERATIONS,
+            seed: 1,
+        };
+
+        let result = prop.eval();
+        assert!(!result.pass);
+
+        let ce = result.counterexample.expect("a failure should be reported");
+        // The smallest input that violates `a < 5` is exactly 5.
+        assert_eq!(ce.inputs, vec![5]);
+        assert_eq!(ce.failing_step, 1);
+    }
+
+    #[test]
+    fn passing_property_reports_no_counterexample() {
+        struct AlwaysHolds;
+        impl PropStep<Ctx> for AlwaysHolds {
+            fn eval(&self, _ctx: &mut Ctx, _inputs: &mut InputSource) -> bool {
+                true
+            }
+        }
+
+        let prop = PropScenario {
+            name: "trivially true".to_string(),
+            steps: vec![Box::new(DrawA), Box::new(AlwaysHolds)],
+            iterations: DEFAULT_ITERATIONS,
+            seed: 7,
+        };
+
+        let result = prop.eval();
+        assert!(result.pass);
+        assert!(result.counterexample.is_none());
+    }
+}
@@ -0,0 +1,118 @@
+//! An interactive, REPL-style runner for feature files. Lines are read one at a
+//! time and buffered until a complete `Scenario:` block has accumulated, at
+//! which point its steps are evaluated immediately and the per-step result and
+//! resulting context are printed. A blank line or end-of-input forces the
+//! current buffer to be evaluated even if the parser would still accept more.
+
+use std::fmt::Debug;
+use std::io::{self, BufRead, Write};
+
+use combine::Parser;
+use combine::easy::{self, Error};
+use combine::stream::state::{SourcePosition, State};
+
+use ast::{Scenario, TestContext};
+use parser::{scenario_parser, BoxedStep};
+
+/// True when the only thing standing between the buffer and a successful parse
+/// is more input — i.e. the block is truncated rather than malformed. This is
+/// how an LL parser signals "feed me more", and it is the cue to keep reading
+/// instead of reporting an error.
+fn needs_more_input(errors: &easy::Errors<char, &str, SourcePosition>) -> bool {
+    errors.errors.iter().any(|e| *e == Error::end_of_input())
+}
+
+/// Evaluate one accumulated scenario, printing each step's pass/fail status and
+/// the context left behind.
+fn run_scenario<C>(scenario: &Scenario<C>)
+where
+    C: TestContext + Debug,
+{
+    match scenario.name {
+        Some(ref name) => println!("Scenario: {}", name),
+        None => println!("Scenario:"),
+    }
+
+    let mut context = C::new();
+    for (i, &(ref step, ref arg)) in scenario.steps.iter().enumerate() {
+        let pass = step.eval(&mut context, arg);
+        println!("  step {}: {}", i + 1, if pass { "pass" } else { "fail" });
+        if !pass {
+            break;
+        }
+    }
+    println!("  context: {:?}", context);
+}
+
+/// Read a feature file incrementally from `reader`, evaluating each `Scenario`
+/// block as soon as it is complete. After a block is run the buffer is cleared
+/// and the session continues with a fresh context for the next block.
+pub fn run_interactive<R, C, GP, WP, TP>(
+    mut reader: R,
+    given: GP,
+    when: WP,
+    then: TP,
+) -> io::Result<()>
+where
+    R: BufRead,
+    C: TestContext + Debug + 'static,
+    for<'a> GP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+    for<'a> WP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+    for<'a> TP: Parser<Input = State<&'a str>, Output = BoxedStep<C>> + Clone,
+{
+    let stdout = io::stdout();
+    let mut buffer = String::new();
+
+    loop {
+        let mut line = String::new();
+        let read = reader.read_line(&mut line)?;
+        let eof = read == 0;
+        let blank = line.trim().is_empty();
+
+        if !eof {
+            buffer.push_str(&line);
+        }
+
+        // A blank line or EOF is an explicit "evaluate now" trigger; otherwise
+        // we only evaluate once the block parses cleanly with nothing left over.
+        let force = blank || eof;
+
+        if buffer.trim().is_empty() {
+            if eof {
+                break;
+            }
+            buffer.clear();
+            continue;
+        }
+
+        let parsed = scenario_parser(given.clone(), when.clone(), then.clone())
+            .easy_parse(State::new(buffer.as_str()));
+
+        match parsed {
+            Ok((scenario, rest)) => {
+                if force || rest.input.trim().is_empty() {
+                    run_scenario(&scenario);
+                    buffer.clear();
+                }
+            }
+            Err(ref errors) if needs_more_input(errors) && !force => {
+                // Truncated block: wait for the next line.
+            }
+            Err(errors) => {
+                if force {
+                    let pos = errors.position;
+                    println!("parse error at line {}, column {}", pos.line, pos.column);
+                    buffer.clear();
+                }
+            }
+        }
+
+        let _ = stdout.lock().flush();
+
+        if eof {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -3,13 +3,15 @@ extern crate combine;
 use combine::{Parser, many1};
 use combine::easy::Error;
 use combine::stream::state::State;
-use combine::char::{string, digit};
+use combine::char::{string, digit, alpha_num};
 
 extern crate rherkin;
 //use rherkin::feature;
 //use rherkin::scenario::{self, Step, BoxedStep, TestContext};
 use rherkin::{ast, parser};
 
+use std::collections::HashMap;
+
 // An rpn calculator, something we can write tests for.
 #[derive(Debug)]
 pub struct Calculator {
@@ -17,7 +19,11 @@ pub struct Calculator {
     pub current: Vec<u32>,
 
     /// The data stack
-    pub stack: Vec<u32>
+    pub stack: Vec<u32>,
+
+    /// Numbers drawn by a `PropSpec`'s generator steps, keyed by the name used
+    /// in the feature file.
+    pub numbers: HashMap<String, i64>
 }
 
 #[derive(Clone, Debug)]
@@ -85,17 +91,24 @@ impl ast::TestContext for Calculator {
     fn new() -> Calculator {
         Calculator {
             current: vec!(),
-            stack: vec!()
+            stack: vec!(),
+            numbers: HashMap::new()
         }
     }
 }
 
+impl rherkin::propspec::PropContext for Calculator {
+    fn set_number(&mut self, name: &str, value: i64) {
+        self.numbers.insert(name.to_string(), value);
+    }
+}
+
 mod steps {
     use super::*;
 
     pub struct Clear { }
     impl ast::Step<Calculator> for Clear {
-        fn eval(&self, calc: &mut Calculator) -> bool {
+        fn eval(&self, calc: &mut Calculator, _arg: &ast::StepArg) -> bool {
             println!("Clear");
             calc.current = vec!();
             calc.stack = vec!();
@@ -105,7 +118,7 @@ mod steps {
 
     pub struct Press { pub button: Button }
     impl ast::Step<Calculator> for Press {
-        fn eval(&self, calc: &mut Calculator) -> bool {
+        fn eval(&self, calc: &mut Calculator, _arg: &ast::StepArg) -> bool {
             println!("Press {:?}", self.button);
             calc.press(&self.button)
         }
@@ -113,7 +126,7 @@ mod steps {
 
     pub struct CheckDisplay { pub expected: String }
     impl ast::Step<Calculator> for CheckDisplay {
-        fn eval(&self, calc: &mut Calculator) -> bool {
+        fn eval(&self, calc: &mut Calculator, _arg: &ast::StepArg) -> bool {
             let actual = calc.stack.last();
             println!("Check display: expected {:?}, actual {:#?}", self.expected, actual);
             match actual {
@@ -123,6 +136,37 @@ mod steps {
         }
     }
 
+    /// Key in the number a `PropSpec` generator drew under `name`, leaving its
+    /// digits in the entry buffer for a following `Enter`/`Plus` to flush.
+    pub struct EnterNumber { pub name: String }
+    impl ast::Step<Calculator> for EnterNumber {
+        fn eval(&self, calc: &mut Calculator, _arg: &ast::StepArg) -> bool {
+            let n = calc.numbers.get(&self.name).cloned().unwrap_or(0);
+            let mut digits = vec!();
+            let mut m = n;
+            if m == 0 {
+                digits.push(0);
+            }
+            while m > 0 {
+                digits.push((m % 10) as u32);
+                m /= 10;
+            }
+            digits.reverse();
+            calc.current.extend(digits);
+            true
+        }
+    }
+
+    pub struct CheckLessThan { pub bound: u32 }
+    impl ast::Step<Calculator> for CheckLessThan {
+        fn eval(&self, calc: &mut Calculator, _arg: &ast::StepArg) -> bool {
+            match calc.stack.last() {
+                Some(n) => *n < self.bound,
+                None => false
+            }
+        }
+    }
+
 }
 
 
@@ -192,20 +236,75 @@ Then the display should read 2
 }
 
 
-// fn proptests() {
-//     let spec = r#"
-// Feature: RPN Calculator Property Specs
+#[test]
+fn proptests() {
+    let spec = r#"Feature: RPN Calculator Property Specs
+
+PropSpec: arbitrary addition
+Given a fresh calculator
+And a number A less than 10000
+And a number B less than 10000
+When I enter the number A
+And I press enter
+And I enter the number B
+And I press plus
+Then the displayed value should be less than 20000
+"#;
+
+    use steps::*;
+
+    let clear = struct_parser! {
+        Clear {
+            _: string("a fresh calculator")
+        }
+    };
+
+    let enter_number = struct_parser! {
+        EnterNumber {
+            _: string("I enter the number "),
+            name: many1(alpha_num())
+        }
+    };
+
+    let press = struct_parser! {
+        Press {
+            _: string("I press "),
+            button: choice! {
+                string("enter").map(|_| Button::Enter),
+                string("plus").map(|_| Button::Plus),
+                string("minus").map(|_| Button::Minus),
+                string("times").map(|_| Button::Times),
+                string("divide").map(|_| Button::Divide)
+            }
+        }
+    };
 
-// PropSpec: arbitrary addition
-// Given a fresh calculator
-// And a number A less than 10000
-// And a number B less than 10000
-// When I enter the number A
-// And I press enter
-// And I enter the number B
-// And I press plus
-// Then the displayed value should be less than 20000
-// "#;
+    let check_less_than = struct_parser! {
+        CheckLessThan {
+            _: string("the displayed value should be less than "),
+            bound: many1::<String, _>(digit()).map(|s| s.parse().unwrap())
+        }
+    };
 
-//     assert!(true)
-// }
+    let given = choice! { clear };
+    // `enter_number` and `press` share the `I ` prefix, so the first is wrapped
+    // in `try` to let a failed match fall through to the second.
+    let when = choice! { combine::try(enter_number), press };
+    let then = choice! { check_less_than };
+
+    let mut p =
+        parser::feature(
+            given.map(|x| parser::BoxedStep { val: Box::new(x) }),
+            when.map (|x| parser::BoxedStep { val: Box::new(x) }),
+            then.map (|x| parser::BoxedStep { val: Box::new(x) }));
+
+    let (f, _remaining) = p.easy_parse(State::new(spec)).unwrap();
+
+    // A + B with both operands below 10000 can never reach 20000, so the
+    // property holds for every drawn pair and no counterexample is reported.
+    let results = f.eval();
+    for r in results {
+        assert!(r.pass);
+        assert!(r.counterexample.is_none());
+    }
+}